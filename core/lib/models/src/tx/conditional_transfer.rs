@@ -0,0 +1,162 @@
+use crate::helpers::{is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount};
+use crate::{Nonce, TokenId};
+use num::BigUint;
+use parity_crypto::digest::sha256;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::Address;
+
+/// A hash-time-locked transfer: spendable by `to` upon revealing a preimage
+/// of `hash_lock` before `timelock`, and refundable back to `from` once
+/// `timelock` has passed. Used to build trustless atomic swaps against
+/// chains (e.g. Bitcoin) that support the equivalent HTLC script.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConditionalTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub token: TokenId,
+    pub amount: BigUint,
+    pub fee: BigUint,
+    pub nonce: Nonce,
+    /// `sha256` of the secret preimage that unlocks the transfer.
+    pub hash_lock: [u8; 32],
+    /// Block number (or unix timestamp, by network convention) after which
+    /// the transfer is no longer redeemable and becomes refundable.
+    pub timelock: u64,
+}
+
+impl ConditionalTransfer {
+    /// Type tag written as the first byte of [`Self::get_bytes`].
+    pub const TX_TYPE: u8 = 12;
+
+    pub fn new(
+        from: Address,
+        to: Address,
+        token: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        nonce: Nonce,
+        hash_lock: [u8; 32],
+        timelock: u64,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            token,
+            amount,
+            fee,
+            nonce,
+            hash_lock,
+            timelock,
+        }
+    }
+
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.push(Self::TX_TYPE);
+        out.extend_from_slice(self.from.as_bytes());
+        out.extend_from_slice(self.to.as_bytes());
+        out.extend_from_slice(&u16::from(self.token).to_be_bytes());
+        out.extend_from_slice(&pack_token_amount(&self.amount));
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out.extend_from_slice(&u32::from(self.nonce).to_be_bytes());
+        out.extend_from_slice(&self.hash_lock);
+        out.extend_from_slice(&self.timelock.to_be_bytes());
+        out
+    }
+
+    pub fn check_correctness(&mut self) -> bool {
+        if self.timelock == 0 {
+            return false;
+        }
+        if self.hash_lock == [0u8; 32] {
+            return false;
+        }
+
+        is_token_amount_packable(&self.amount) && is_fee_amount_packable(&self.fee)
+    }
+
+    /// Whether `preimage` redeems this transfer, i.e. `sha256(preimage) == hash_lock`.
+    pub fn verify_preimage(&self, preimage: &[u8; 32]) -> bool {
+        sha256(preimage).as_ref() == self.hash_lock
+    }
+
+    /// Whether the transfer has passed its timelock and can be refunded to `from`.
+    pub fn is_refundable(&self, current_block: u64) -> bool {
+        current_block >= self.timelock
+    }
+}
+
+/// Circuit-facing operation wrapper, mirroring `TransferOp`/`WithdrawOp`.
+pub struct ConditionalTransferOp {
+    pub tx: ConditionalTransfer,
+}
+
+impl ConditionalTransferOp {
+    pub const CHUNKS: usize = 6;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(hash_lock: [u8; 32], timelock: u64) -> ConditionalTransfer {
+        ConditionalTransfer::new(
+            Address::from([1u8; 20]),
+            Address::from([2u8; 20]),
+            TokenId(0),
+            BigUint::from(100u64),
+            BigUint::from(1u64),
+            Nonce(0),
+            hash_lock,
+            timelock,
+        )
+    }
+
+    #[test]
+    fn valid_conditional_transfer_passes_correctness() {
+        let mut tx = sample([7u8; 32], 1_000);
+        assert!(tx.check_correctness());
+    }
+
+    #[test]
+    fn rejects_zero_timelock_and_zero_hash_lock() {
+        let mut tx = sample([7u8; 32], 0);
+        assert!(!tx.check_correctness());
+
+        let mut tx = sample([0u8; 32], 1_000);
+        assert!(!tx.check_correctness());
+    }
+
+    #[test]
+    fn verify_preimage_matches_hash_lock() {
+        let preimage = [42u8; 32];
+        let mut hash_lock = [0u8; 32];
+        hash_lock.copy_from_slice(&sha256(&preimage));
+        let tx = sample(hash_lock, 1_000);
+
+        assert!(tx.verify_preimage(&preimage));
+        assert!(!tx.verify_preimage(&[0u8; 32]));
+    }
+
+    #[test]
+    fn is_refundable_only_after_timelock() {
+        let tx = sample([7u8; 32], 1_000);
+        assert!(!tx.is_refundable(999));
+        assert!(tx.is_refundable(1_000));
+    }
+
+    #[test]
+    fn min_chunks_matches_op_chunks() {
+        assert_eq!(ConditionalTransferOp::CHUNKS, 6);
+        let tx = crate::tx::FranklinTx::ConditionalTransfer(Box::new(sample([7u8; 32], 1_000)));
+        assert_eq!(tx.min_chunks(), ConditionalTransferOp::CHUNKS);
+    }
+
+    #[test]
+    fn get_bytes_is_deterministic_and_starts_with_tx_type() {
+        let tx = sample([7u8; 32], 1_000);
+        let bytes = tx.get_bytes();
+        assert_eq!(bytes[0], ConditionalTransfer::TX_TYPE);
+        assert_eq!(bytes, tx.get_bytes());
+    }
+}
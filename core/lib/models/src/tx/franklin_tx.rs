@@ -1,13 +1,19 @@
 use crate::Nonce;
 
 use crate::{
-    tx::{ChangePubKey, Close, Transfer, TxEthSignature, TxHash, Withdraw},
+    tx::{
+        ChangePubKey, Close, ConditionalTransfer, OutcomeWithdraw, Transfer, TxEthSignature, TxHash,
+        Withdraw,
+    },
     CloseOp, TokenLike, TransferOp, TxFeeTypes, WithdrawOp,
 };
 use num::BigUint;
 use parity_crypto::digest::sha256;
+use std::collections::HashMap;
 
 use crate::operations::ChangePubKeyOp;
+use crate::tx::conditional_transfer::ConditionalTransferOp;
+use crate::tx::outcome_withdraw::OutcomeWithdrawOp;
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::Address;
 
@@ -17,6 +23,19 @@ pub struct EthSignData {
     pub message: String,
 }
 
+impl EthSignData {
+    /// Verifies `signature` against `account` having signed `message`. See
+    /// [`TxEthSignature::verify_against`] for the EIP-1271 callback.
+    pub fn verify_against(
+        &self,
+        account: Address,
+        call_is_valid_signature: impl FnOnce(Address, &[u8], &[u8]) -> bool,
+    ) -> bool {
+        self.signature
+            .verify_against(account, self.message.as_bytes(), call_is_valid_signature)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignedFranklinTx {
     pub tx: FranklinTx,
@@ -30,6 +49,8 @@ pub enum FranklinTx {
     Withdraw(Box<Withdraw>),
     Close(Box<Close>),
     ChangePubKey(Box<ChangePubKey>),
+    ConditionalTransfer(Box<ConditionalTransfer>),
+    OutcomeWithdraw(Box<OutcomeWithdraw>),
 }
 
 impl From<FranklinTx> for SignedFranklinTx {
@@ -49,6 +70,90 @@ impl std::ops::Deref for SignedFranklinTx {
     }
 }
 
+/// A group of transactions signed once with a single Ethereum message,
+/// rather than one Ethereum signature per transaction.
+///
+/// Only one member of the batch needs to carry a non-zero fee: `total_fee()`
+/// lets the mempool verify that the fee-paying member(s) cover the whole
+/// group, so the remaining transfers can be submitted fee-free.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedFranklinTxBatch {
+    pub txs: Vec<FranklinTx>,
+    pub eth_sign_data: Option<EthSignData>,
+}
+
+impl SignedFranklinTxBatch {
+    pub fn new(txs: Vec<FranklinTx>, eth_sign_data: Option<EthSignData>) -> Self {
+        Self { txs, eth_sign_data }
+    }
+
+    /// Hash of the batch, computed as `sha256` over the concatenated bytes
+    /// of every member transaction, in order.
+    pub fn batch_hash(&self) -> TxHash {
+        let bytes: Vec<u8> = self.txs.iter().flat_map(|tx| tx.get_bytes()).collect();
+
+        let hash = sha256(&bytes);
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&hash);
+        TxHash { data: out }
+    }
+
+    /// Validates every member of the batch and checks that nonces for a
+    /// given account are strictly increasing and gap-free across the batch.
+    /// An empty batch is rejected: there is no "all members apply atomically"
+    /// to guarantee if there are no members.
+    pub fn check_correctness(&mut self) -> bool {
+        if self.txs.is_empty() {
+            return false;
+        }
+
+        let mut last_nonce: HashMap<Address, u32> = HashMap::new();
+
+        for tx in self.txs.iter_mut() {
+            if !tx.check_correctness() {
+                return false;
+            }
+
+            let account = tx.account();
+            let nonce = u32::from(tx.nonce());
+
+            if let Some(&prev) = last_nonce.get(&account) {
+                if nonce != prev + 1 {
+                    return false;
+                }
+            }
+            last_nonce.insert(account, nonce);
+        }
+
+        true
+    }
+
+    /// Sum of the fees declared by every member of the batch, grouped by
+    /// fee token, so that a single transfer can pay for the whole batch.
+    ///
+    /// This only totals what the batch's members declare; it does not check
+    /// that total against the batch's real required fee, since computing
+    /// that requires pricing data (a fee ticker) this crate does not have.
+    /// The mempool must perform that cross-check before accepting the batch.
+    pub fn total_fee(&self) -> HashMap<TokenLike, BigUint> {
+        let mut fees = HashMap::new();
+
+        for tx in &self.txs {
+            if let Some((_fee_type, token, _recipient, fee)) = tx.get_fee_info() {
+                let total = fees.entry(token).or_insert_with(BigUint::default);
+                *total += fee;
+            }
+        }
+
+        fees
+    }
+
+    /// Total number of chunks required to process every member of the batch.
+    pub fn min_chunks(&self) -> usize {
+        self.txs.iter().map(FranklinTx::min_chunks).sum()
+    }
+}
+
 impl FranklinTx {
     pub fn hash(&self) -> TxHash {
         let bytes = match self {
@@ -56,6 +161,8 @@ impl FranklinTx {
             FranklinTx::Withdraw(tx) => tx.get_bytes(),
             FranklinTx::Close(tx) => tx.get_bytes(),
             FranklinTx::ChangePubKey(tx) => tx.get_bytes(),
+            FranklinTx::ConditionalTransfer(tx) => tx.get_bytes(),
+            FranklinTx::OutcomeWithdraw(tx) => tx.get_bytes(),
         };
 
         let hash = sha256(&bytes);
@@ -70,6 +177,8 @@ impl FranklinTx {
             FranklinTx::Withdraw(tx) => tx.from,
             FranklinTx::Close(tx) => tx.account,
             FranklinTx::ChangePubKey(tx) => tx.account,
+            FranklinTx::ConditionalTransfer(tx) => tx.from,
+            FranklinTx::OutcomeWithdraw(tx) => tx.from,
         }
     }
 
@@ -79,6 +188,8 @@ impl FranklinTx {
             FranklinTx::Withdraw(tx) => tx.nonce,
             FranklinTx::Close(tx) => tx.nonce,
             FranklinTx::ChangePubKey(tx) => tx.nonce,
+            FranklinTx::ConditionalTransfer(tx) => tx.nonce,
+            FranklinTx::OutcomeWithdraw(tx) => tx.nonce,
         }
     }
 
@@ -88,6 +199,8 @@ impl FranklinTx {
             FranklinTx::Withdraw(tx) => tx.check_correctness(),
             FranklinTx::Close(tx) => tx.check_correctness(),
             FranklinTx::ChangePubKey(tx) => tx.check_correctness(),
+            FranklinTx::ConditionalTransfer(tx) => tx.check_correctness(),
+            FranklinTx::OutcomeWithdraw(tx) => tx.check_correctness(),
         }
     }
 
@@ -97,6 +210,8 @@ impl FranklinTx {
             FranklinTx::Withdraw(tx) => tx.get_bytes(),
             FranklinTx::Close(tx) => tx.get_bytes(),
             FranklinTx::ChangePubKey(tx) => tx.get_bytes(),
+            FranklinTx::ConditionalTransfer(tx) => tx.get_bytes(),
+            FranklinTx::OutcomeWithdraw(tx) => tx.get_bytes(),
         }
     }
 
@@ -106,12 +221,14 @@ impl FranklinTx {
             FranklinTx::Withdraw(_) => WithdrawOp::CHUNKS,
             FranklinTx::Close(_) => CloseOp::CHUNKS,
             FranklinTx::ChangePubKey(_) => ChangePubKeyOp::CHUNKS,
+            FranklinTx::ConditionalTransfer(_) => ConditionalTransferOp::CHUNKS,
+            FranklinTx::OutcomeWithdraw(_) => OutcomeWithdrawOp::CHUNKS,
         }
     }
 
     pub fn is_withdraw(&self) -> bool {
         match self {
-            FranklinTx::Withdraw(_) => true,
+            FranklinTx::Withdraw(_) | FranklinTx::OutcomeWithdraw(_) => true,
             _ => false,
         }
     }
@@ -145,7 +262,99 @@ impl FranklinTx {
                 transfer.to,
                 transfer.fee.clone(),
             )),
+            FranklinTx::ConditionalTransfer(tx) => Some((
+                TxFeeTypes::Transfer,
+                TokenLike::Id(tx.token),
+                tx.to,
+                tx.fee.clone(),
+            )),
+            FranklinTx::OutcomeWithdraw(tx) => {
+                let fee_type = if tx.fast {
+                    TxFeeTypes::FastWithdraw
+                } else {
+                    TxFeeTypes::Withdraw
+                };
+
+                Some((fee_type, TokenLike::Id(tx.token), tx.to, tx.fee.clone()))
+            }
             _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+    use crate::TokenId;
+
+    fn conditional_transfer_tx(from: Address, nonce: u32, fee: u64) -> FranklinTx {
+        FranklinTx::ConditionalTransfer(Box::new(ConditionalTransfer::new(
+            from,
+            Address::from([9u8; 20]),
+            TokenId(0),
+            BigUint::from(100u64),
+            BigUint::from(fee),
+            Nonce(nonce),
+            [7u8; 32],
+            1_000,
+        )))
+    }
+
+    #[test]
+    fn rejects_empty_batch() {
+        let mut batch = SignedFranklinTxBatch::new(vec![], None);
+        assert!(!batch.check_correctness());
+    }
+
+    #[test]
+    fn accepts_batch_with_gap_free_increasing_nonces() {
+        let from = Address::from([1u8; 20]);
+        let mut batch = SignedFranklinTxBatch::new(
+            vec![
+                conditional_transfer_tx(from, 0, 10),
+                conditional_transfer_tx(from, 1, 0),
+                conditional_transfer_tx(from, 2, 0),
+            ],
+            None,
+        );
+
+        assert!(batch.check_correctness());
+    }
+
+    #[test]
+    fn rejects_batch_with_nonce_gap() {
+        let from = Address::from([1u8; 20]);
+        let mut batch = SignedFranklinTxBatch::new(
+            vec![conditional_transfer_tx(from, 0, 10), conditional_transfer_tx(from, 2, 0)],
+            None,
+        );
+
+        assert!(!batch.check_correctness());
+    }
+
+    #[test]
+    fn total_fee_sums_members_by_token() {
+        let from = Address::from([1u8; 20]);
+        let batch = SignedFranklinTxBatch::new(
+            vec![
+                conditional_transfer_tx(from, 0, 10),
+                conditional_transfer_tx(from, 1, 0),
+            ],
+            None,
+        );
+
+        let fees = batch.total_fee();
+        assert_eq!(fees.get(&TokenLike::Id(TokenId(0))), Some(&BigUint::from(10u64)));
+    }
+
+    #[test]
+    fn min_chunks_sums_member_chunks() {
+        let from = Address::from([1u8; 20]);
+        let batch = SignedFranklinTxBatch::new(
+            vec![conditional_transfer_tx(from, 0, 10), conditional_transfer_tx(from, 1, 0)],
+            None,
+        );
+
+        assert_eq!(batch.min_chunks(), 2 * ConditionalTransferOp::CHUNKS);
+    }
+}
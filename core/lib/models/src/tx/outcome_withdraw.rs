@@ -0,0 +1,265 @@
+use crate::helpers::{is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount};
+use crate::{Nonce, TokenId};
+use num::BigUint;
+use parity_crypto::digest::sha256;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::Address;
+
+/// A withdrawal that only becomes spendable once an oracle attests to the
+/// outcome of a named event, implementing the discreet-log-contract (DLC)
+/// pattern: the withdrawal signature is adaptor-encrypted to the point the
+/// oracle is expected to reveal, and is only meant to decrypt into a valid
+/// signature once that attestation is published. If the oracle never
+/// attests, `timeout` lets the funds be refunded to `from`.
+///
+/// Note: the actual adaptor-signature scalar arithmetic is not implemented
+/// in this crate yet (see [`Self::combine_with_attestation`]); today this
+/// type only carries and shape-validates the encrypted signature, and
+/// [`Self::is_verified_by`] fails closed rather than certifying it as
+/// oracle-attested. This variant is not yet safe to wire into anything that
+/// moves funds.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OutcomeWithdraw {
+    pub from: Address,
+    pub to: Address,
+    pub token: TokenId,
+    pub amount: BigUint,
+    pub fee: BigUint,
+    pub nonce: Nonce,
+    pub fast: bool,
+    /// The oracle's public key, used to recompute the attestation point.
+    pub oracle_pubkey: Vec<u8>,
+    /// Identifier of the event the oracle will attest to.
+    pub event_id: String,
+    /// The outcome label this withdrawal is conditioned on.
+    pub outcome: String,
+    /// Adaptor signature, encrypted to the oracle's anticipated attestation
+    /// point for `(event_id, outcome)`.
+    pub encrypted_signature: Vec<u8>,
+    /// Block number after which the withdrawal is no longer attestable and
+    /// becomes refundable to `from`.
+    pub timeout: u64,
+}
+
+/// Expected length of a serialized compressed secp256k1 oracle public key.
+const ORACLE_PUBKEY_LEN: usize = 33;
+/// Expected length of a serialized `(R, s)` adaptor signature: a curve point
+/// plus a scalar.
+const ADAPTOR_SIGNATURE_LEN: usize = 64;
+
+impl OutcomeWithdraw {
+    /// Type tag written as the first byte of [`Self::get_bytes`]; the tag
+    /// after `ConditionalTransfer::TX_TYPE` (12).
+    pub const TX_TYPE: u8 = 13;
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        from: Address,
+        to: Address,
+        token: TokenId,
+        amount: BigUint,
+        fee: BigUint,
+        nonce: Nonce,
+        fast: bool,
+        oracle_pubkey: Vec<u8>,
+        event_id: String,
+        outcome: String,
+        encrypted_signature: Vec<u8>,
+        timeout: u64,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            token,
+            amount,
+            fee,
+            nonce,
+            fast,
+            oracle_pubkey,
+            event_id,
+            outcome,
+            encrypted_signature,
+            timeout,
+        }
+    }
+
+    /// Returns `sha256(oracle_pubkey || event_id || outcome)`, a 32-byte
+    /// digest of the inputs the oracle's attestation point depends on. It is
+    /// not that curve point: no curve arithmetic is performed here.
+    pub fn attestation_point(&self) -> [u8; 32] {
+        let mut preimage = Vec::with_capacity(self.oracle_pubkey.len() + self.event_id.len() + self.outcome.len());
+        preimage.extend_from_slice(&self.oracle_pubkey);
+        preimage.extend_from_slice(self.event_id.as_bytes());
+        preimage.extend_from_slice(self.outcome.as_bytes());
+
+        let hash = sha256(&preimage);
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&hash);
+        out
+    }
+
+    /// Combines `encrypted_signature` with the oracle's published
+    /// attestation scalar.
+    ///
+    /// This is a placeholder byte-combination, not real adaptor-signature
+    /// decryption: completing a DLC adaptor signature requires scalar
+    /// arithmetic on the curve the signature was adaptor-encrypted over,
+    /// which this crate does not implement yet. The result must not be
+    /// treated as a verified or spendable signature.
+    pub fn combine_with_attestation(&self, attestation_scalar: &[u8]) -> Vec<u8> {
+        self.encrypted_signature
+            .iter()
+            .zip(attestation_scalar.iter().cycle())
+            .map(|(byte, scalar_byte)| byte ^ scalar_byte)
+            .collect()
+    }
+
+    /// Whether `attestation_scalar` proves the oracle attested to this
+    /// withdrawal's `(event_id, outcome)`, i.e. whether `encrypted_signature`
+    /// is now a complete, spendable signature.
+    ///
+    /// Real adaptor-signature completion requires scalar arithmetic over the
+    /// curve `encrypted_signature` was encrypted on, which this crate does
+    /// not implement yet — [`Self::combine_with_attestation`] is a
+    /// placeholder, not that arithmetic. Until it is, this fails closed,
+    /// mirroring the unimplemented-Schnorr stance in
+    /// `eth_signature::verify_schnorr_signature`: no caller should treat an
+    /// `OutcomeWithdraw` as oracle-verified, and funds must not move off one,
+    /// until this method is backed by real verification.
+    pub fn is_verified_by(&self, _attestation_scalar: &[u8]) -> bool {
+        false
+    }
+
+    /// Whether the withdrawal has passed its timeout and can be refunded.
+    pub fn is_refundable(&self, current_block: u64) -> bool {
+        current_block >= self.timeout
+    }
+
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.push(Self::TX_TYPE);
+        out.extend_from_slice(self.from.as_bytes());
+        out.extend_from_slice(self.to.as_bytes());
+        out.extend_from_slice(&u16::from(self.token).to_be_bytes());
+        out.extend_from_slice(&pack_token_amount(&self.amount));
+        out.extend_from_slice(&pack_fee_amount(&self.fee));
+        out.extend_from_slice(&u32::from(self.nonce).to_be_bytes());
+
+        out.extend_from_slice(&(self.oracle_pubkey.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.oracle_pubkey);
+        out.extend_from_slice(&(self.event_id.len() as u32).to_be_bytes());
+        out.extend_from_slice(self.event_id.as_bytes());
+        out.extend_from_slice(&(self.outcome.len() as u32).to_be_bytes());
+        out.extend_from_slice(self.outcome.as_bytes());
+        out.extend_from_slice(&(self.encrypted_signature.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.encrypted_signature);
+        out.extend_from_slice(&self.timeout.to_be_bytes());
+
+        out
+    }
+
+    pub fn check_correctness(&mut self) -> bool {
+        if self.event_id.is_empty() || self.outcome.is_empty() {
+            return false;
+        }
+        if self.oracle_pubkey.len() != ORACLE_PUBKEY_LEN {
+            return false;
+        }
+        if self.encrypted_signature.len() != ADAPTOR_SIGNATURE_LEN {
+            return false;
+        }
+
+        is_token_amount_packable(&self.amount) && is_fee_amount_packable(&self.fee)
+    }
+}
+
+/// Circuit-facing operation wrapper, mirroring `WithdrawOp`.
+pub struct OutcomeWithdrawOp {
+    pub tx: OutcomeWithdraw,
+}
+
+impl OutcomeWithdrawOp {
+    pub const CHUNKS: usize = 6;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(event_id: &str, outcome: &str, oracle_pubkey_len: usize, signature_len: usize) -> OutcomeWithdraw {
+        OutcomeWithdraw::new(
+            Address::from([1u8; 20]),
+            Address::from([2u8; 20]),
+            TokenId(0),
+            BigUint::from(100u64),
+            BigUint::from(1u64),
+            Nonce(0),
+            false,
+            vec![7u8; oracle_pubkey_len],
+            event_id.to_string(),
+            outcome.to_string(),
+            vec![9u8; signature_len],
+            1_000,
+        )
+    }
+
+    #[test]
+    fn valid_outcome_withdraw_passes_correctness() {
+        let mut tx = sample("event-1", "yes", ORACLE_PUBKEY_LEN, ADAPTOR_SIGNATURE_LEN);
+        assert!(tx.check_correctness());
+    }
+
+    #[test]
+    fn rejects_empty_event_or_outcome() {
+        let mut tx = sample("", "yes", ORACLE_PUBKEY_LEN, ADAPTOR_SIGNATURE_LEN);
+        assert!(!tx.check_correctness());
+
+        let mut tx = sample("event-1", "", ORACLE_PUBKEY_LEN, ADAPTOR_SIGNATURE_LEN);
+        assert!(!tx.check_correctness());
+    }
+
+    #[test]
+    fn rejects_malformed_oracle_pubkey_or_signature_shape() {
+        let mut tx = sample("event-1", "yes", ORACLE_PUBKEY_LEN - 1, ADAPTOR_SIGNATURE_LEN);
+        assert!(!tx.check_correctness());
+
+        let mut tx = sample("event-1", "yes", ORACLE_PUBKEY_LEN, ADAPTOR_SIGNATURE_LEN - 1);
+        assert!(!tx.check_correctness());
+    }
+
+    #[test]
+    fn min_chunks_matches_op_chunks() {
+        assert_eq!(OutcomeWithdrawOp::CHUNKS, 6);
+        let tx = crate::tx::FranklinTx::OutcomeWithdraw(Box::new(sample(
+            "event-1",
+            "yes",
+            ORACLE_PUBKEY_LEN,
+            ADAPTOR_SIGNATURE_LEN,
+        )));
+        assert_eq!(tx.min_chunks(), OutcomeWithdrawOp::CHUNKS);
+    }
+
+    #[test]
+    fn get_bytes_is_deterministic_and_starts_with_tx_type() {
+        let tx = sample("event-1", "yes", ORACLE_PUBKEY_LEN, ADAPTOR_SIGNATURE_LEN);
+        let bytes = tx.get_bytes();
+        assert_eq!(bytes[0], OutcomeWithdraw::TX_TYPE);
+        assert_eq!(bytes, tx.get_bytes());
+    }
+
+    #[test]
+    fn is_refundable_only_after_timeout() {
+        let tx = sample("event-1", "yes", ORACLE_PUBKEY_LEN, ADAPTOR_SIGNATURE_LEN);
+        assert!(!tx.is_refundable(999));
+        assert!(tx.is_refundable(1_000));
+    }
+
+    #[test]
+    fn is_verified_by_fails_closed_until_adaptor_verification_is_implemented() {
+        let tx = sample("event-1", "yes", ORACLE_PUBKEY_LEN, ADAPTOR_SIGNATURE_LEN);
+        let attestation_scalar = tx.attestation_point();
+
+        assert!(!tx.is_verified_by(&attestation_scalar));
+        assert!(!tx.is_verified_by(&[]));
+    }
+}
@@ -0,0 +1,525 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use num::BigUint;
+use zksync_basic_types::Address;
+
+use crate::helpers::{is_fee_amount_packable, is_token_amount_packable};
+use crate::tx::{FranklinTx, SignedFranklinTxBatch, Transfer};
+use crate::{Nonce, TokenId, TokenLike};
+
+/// URI scheme used by payment requests, e.g. `zksync:0x1234..?amount=1&token=ETH`.
+const URI_SCHEME: &str = "zksync";
+
+/// Parameter-name prefix that, per ZIP-321, marks a parameter as mandatory
+/// to understand: an unrecognized `req-`-prefixed key invalidates the whole
+/// request. Unrecognized keys without this prefix are safely ignorable.
+const REQUIRED_PARAM_PREFIX: &str = "req-";
+
+/// A single recipient of a payment request, used both for the common
+/// one-recipient case and for the indexed `address.N`/`amount.N` form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentRequestRecipient {
+    pub address: Address,
+    pub amount: BigUint,
+}
+
+/// A merchant-issued, unsigned request for a transfer (or set of transfers).
+///
+/// Mirrors the ZIP-321 payment URI scheme: a `zksync:<address>?amount=..`
+/// string deterministically describes what should be paid, but carries no
+/// `from` or signature, and its `nonce` is optional — the wallet that
+/// receives it fills those in and signs the result into a
+/// [`crate::tx::FranklinTx`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentRequest {
+    pub recipients: Vec<PaymentRequestRecipient>,
+    pub token: TokenLike,
+    pub fee: Option<BigUint>,
+    pub nonce: Option<Nonce>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaymentRequestError {
+    InvalidScheme,
+    MissingRecipient,
+    MissingToken,
+    DuplicateParameter(String),
+    UnknownParameter(String),
+    InvalidAddress(String),
+    InvalidAmount(String),
+    InvalidNonce(String),
+    AmountNotPackable(String),
+}
+
+impl fmt::Display for PaymentRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaymentRequestError::InvalidScheme => {
+                write!(f, "URI does not use the '{}:' scheme", URI_SCHEME)
+            }
+            PaymentRequestError::MissingRecipient => write!(f, "payment request has no recipient"),
+            PaymentRequestError::MissingToken => write!(f, "payment request has no token"),
+            PaymentRequestError::DuplicateParameter(key) => {
+                write!(f, "duplicate parameter '{}'", key)
+            }
+            PaymentRequestError::UnknownParameter(key) => {
+                write!(f, "unknown required parameter '{}'", key)
+            }
+            PaymentRequestError::InvalidAddress(value) => {
+                write!(f, "invalid address '{}'", value)
+            }
+            PaymentRequestError::InvalidAmount(value) => write!(f, "invalid amount '{}'", value),
+            PaymentRequestError::InvalidNonce(value) => write!(f, "invalid nonce '{}'", value),
+            PaymentRequestError::AmountNotPackable(value) => {
+                write!(f, "amount '{}' is not representable in the packable amount encoding", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PaymentRequestError {}
+
+/// Builder for [`PaymentRequest`], mirroring the `*Builder` constructors used
+/// elsewhere for multi-field, partially-optional values.
+#[derive(Debug, Default)]
+pub struct PaymentRequestBuilder {
+    recipients: Vec<PaymentRequestRecipient>,
+    token: Option<TokenLike>,
+    fee: Option<BigUint>,
+    nonce: Option<Nonce>,
+    message: Option<String>,
+}
+
+impl PaymentRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn recipient(mut self, address: Address, amount: BigUint) -> Self {
+        self.recipients.push(PaymentRequestRecipient { address, amount });
+        self
+    }
+
+    pub fn token(mut self, token: TokenLike) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    pub fn fee(mut self, fee: BigUint) -> Self {
+        self.fee = Some(fee);
+        self
+    }
+
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub fn message(mut self, message: String) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    pub fn build(self) -> Result<PaymentRequest, PaymentRequestError> {
+        if self.recipients.is_empty() {
+            return Err(PaymentRequestError::MissingRecipient);
+        }
+
+        Ok(PaymentRequest {
+            recipients: self.recipients,
+            token: self.token.ok_or(PaymentRequestError::MissingToken)?,
+            fee: self.fee,
+            nonce: self.nonce,
+            message: self.message,
+        })
+    }
+}
+
+impl PaymentRequest {
+    /// Serializes this request into a `zksync:` URI. The first recipient is
+    /// encoded as the path component; any further recipients are encoded as
+    /// indexed `address.N`/`amount.N` parameters.
+    pub fn to_uri(&self) -> String {
+        let mut uri = format!("{}:{}", URI_SCHEME, format_address(&self.recipients[0].address));
+        let mut params = vec![
+            format!("amount={}", self.recipients[0].amount),
+            format!("token={}", self.token),
+        ];
+
+        for (offset, recipient) in self.recipients.iter().enumerate().skip(1) {
+            params.push(format!("address.{}={}", offset, format_address(&recipient.address)));
+            params.push(format!("amount.{}={}", offset, recipient.amount));
+        }
+
+        if let Some(fee) = &self.fee {
+            params.push(format!("fee={}", fee));
+        }
+        if let Some(nonce) = &self.nonce {
+            params.push(format!("nonce={}", u32::from(*nonce)));
+        }
+        if let Some(message) = &self.message {
+            params.push(format!("message={}", percent_encode(message)));
+        }
+
+        uri.push('?');
+        uri.push_str(&params.join("&"));
+        uri
+    }
+
+    /// Parses a `zksync:` payment URI produced by [`Self::to_uri`] (or by a
+    /// compatible merchant integration).
+    pub fn from_uri(uri: &str) -> Result<Self, PaymentRequestError> {
+        let rest = uri
+            .strip_prefix(URI_SCHEME)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .ok_or(PaymentRequestError::InvalidScheme)?;
+
+        let (address_part, query) = match rest.find('?') {
+            Some(pos) => (&rest[..pos], &rest[pos + 1..]),
+            None => (rest, ""),
+        };
+
+        let primary_address = parse_address(address_part)?;
+
+        let mut seen_keys = HashSet::new();
+        let mut indexed_addresses = std::collections::HashMap::new();
+        let mut indexed_amounts = std::collections::HashMap::new();
+        let mut primary_amount = None;
+        let mut token = None;
+        let mut fee = None;
+        let mut nonce = None;
+        let mut message = None;
+
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next().unwrap_or_default();
+            let raw_value = parts.next().unwrap_or_default();
+            let value = percent_decode(raw_value);
+
+            if let Some((base, index)) = split_indexed_key(key) {
+                match base {
+                    "address" => {
+                        indexed_addresses.insert(index, parse_address(&value)?);
+                    }
+                    "amount" => {
+                        indexed_amounts.insert(index, parse_amount(&value)?);
+                    }
+                    _ => reject_if_required(key)?,
+                }
+                continue;
+            }
+
+            if !seen_keys.insert(key.to_string()) {
+                return Err(PaymentRequestError::DuplicateParameter(key.to_string()));
+            }
+
+            match key {
+                "amount" => primary_amount = Some(parse_amount(&value)?),
+                "token" => token = Some(TokenLike::from(value)),
+                "fee" => fee = Some(parse_fee(&value)?),
+                "nonce" => {
+                    let raw: u32 = value
+                        .parse()
+                        .map_err(|_| PaymentRequestError::InvalidNonce(value.clone()))?;
+                    nonce = Some(Nonce(raw));
+                }
+                "message" => message = Some(value),
+                _ => reject_if_required(key)?,
+            }
+        }
+
+        let mut recipients = vec![PaymentRequestRecipient {
+            address: primary_address,
+            amount: primary_amount.ok_or_else(|| PaymentRequestError::InvalidAmount(String::new()))?,
+        }];
+
+        let mut indices: Vec<u32> = indexed_addresses.keys().chain(indexed_amounts.keys()).copied().collect();
+        indices.sort_unstable();
+        indices.dedup();
+        for index in indices {
+            let address = indexed_addresses
+                .remove(&index)
+                .ok_or_else(|| PaymentRequestError::InvalidAddress(format!("address.{}", index)))?;
+            let amount = indexed_amounts
+                .remove(&index)
+                .ok_or_else(|| PaymentRequestError::InvalidAmount(format!("amount.{}", index)))?;
+            recipients.push(PaymentRequestRecipient { address, amount });
+        }
+
+        Ok(PaymentRequest {
+            recipients,
+            token: token.ok_or(PaymentRequestError::MissingToken)?,
+            fee,
+            nonce,
+            message,
+        })
+    }
+
+    /// Lowers this request into one unsigned [`Transfer`] per recipient,
+    /// taking `from`, the token id and the nonces from the arguments; this
+    /// request's own `token` and `nonce` fields are not used.
+    ///
+    /// Only the first transfer carries this request's `fee` (defaulting to
+    /// zero if unset); the rest are fee-free, so a single signature can pay
+    /// for the whole group once these are wrapped into a
+    /// [`SignedFranklinTxBatch`] (see [`Self::into_batch`]). Nonces are
+    /// assigned sequentially starting at `starting_nonce`, matching the
+    /// strictly-increasing, gap-free sequence [`SignedFranklinTxBatch::check_correctness`]
+    /// requires for a single account.
+    pub fn into_transfers(&self, from: Address, token: TokenId, starting_nonce: Nonce) -> Vec<Transfer> {
+        let mut nonce = u32::from(starting_nonce);
+
+        self.recipients
+            .iter()
+            .enumerate()
+            .map(|(index, recipient)| {
+                let fee = if index == 0 {
+                    self.fee.clone().unwrap_or_else(|| BigUint::from(0u64))
+                } else {
+                    BigUint::from(0u64)
+                };
+
+                let transfer = Transfer::new(from, recipient.address, token, recipient.amount.clone(), fee, Nonce(nonce));
+                nonce += 1;
+                transfer
+            })
+            .collect()
+    }
+
+    /// Convenience wrapper around [`Self::into_transfers`] that packs the
+    /// resulting transfers into an unsigned [`SignedFranklinTxBatch`],
+    /// ready for a wallet to sign as one group.
+    pub fn into_batch(&self, from: Address, token: TokenId, starting_nonce: Nonce) -> SignedFranklinTxBatch {
+        let txs = self
+            .into_transfers(from, token, starting_nonce)
+            .into_iter()
+            .map(|transfer| FranklinTx::Transfer(Box::new(transfer)))
+            .collect();
+
+        SignedFranklinTxBatch::new(txs, None)
+    }
+}
+
+/// Rejects `key` only if it is `req-`-prefixed (mandatory-to-understand,
+/// per ZIP-321); any other unrecognized key is silently ignored.
+fn reject_if_required(key: &str) -> Result<(), PaymentRequestError> {
+    if key.starts_with(REQUIRED_PARAM_PREFIX) {
+        return Err(PaymentRequestError::UnknownParameter(key.to_string()));
+    }
+    Ok(())
+}
+
+fn split_indexed_key(key: &str) -> Option<(&str, u32)> {
+    let dot = key.find('.')?;
+    let (base, index) = key.split_at(dot);
+    let index: u32 = index[1..].parse().ok()?;
+    Some((base, index))
+}
+
+fn parse_address(value: &str) -> Result<Address, PaymentRequestError> {
+    let hex = value.strip_prefix("0x").unwrap_or(value);
+    hex.parse::<Address>()
+        .map_err(|_| PaymentRequestError::InvalidAddress(value.to_string()))
+}
+
+fn parse_amount(value: &str) -> Result<BigUint, PaymentRequestError> {
+    let amount = value
+        .parse::<BigUint>()
+        .map_err(|_| PaymentRequestError::InvalidAmount(value.to_string()))?;
+    if !is_token_amount_packable(&amount) {
+        return Err(PaymentRequestError::AmountNotPackable(value.to_string()));
+    }
+    Ok(amount)
+}
+
+fn parse_fee(value: &str) -> Result<BigUint, PaymentRequestError> {
+    let fee = value
+        .parse::<BigUint>()
+        .map_err(|_| PaymentRequestError::InvalidAmount(value.to_string()))?;
+    if !is_fee_amount_packable(&fee) {
+        return Err(PaymentRequestError::AmountNotPackable(value.to_string()));
+    }
+    Ok(fee)
+}
+
+fn format_address(address: &Address) -> String {
+    format!("{:#x}", address)
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        // Slice the raw bytes (never panics) rather than `value[..]`, whose
+        // range indexing panics if `i + 1`/`i + 3` don't land on a UTF-8
+        // char boundary — which a malformed or un-encoded `%` next to a
+        // multi-byte character would otherwise trigger.
+        let hex_digits = (i + 2 < bytes.len())
+            .then(|| &bytes[i + 1..i + 3])
+            .and_then(|raw| std::str::from_utf8(raw).ok());
+
+        if bytes[i] == b'%' {
+            if let Some(byte) = hex_digits.and_then(|digits| u8::from_str_radix(digits, 16).ok()) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn address(byte: u8) -> Address {
+        Address::from([byte; 20])
+    }
+
+    #[test]
+    fn round_trip_single_recipient() {
+        let request = PaymentRequestBuilder::new()
+            .recipient(address(1), BigUint::from(1_000_000u64))
+            .token(TokenLike::Symbol("ETH".to_string()))
+            .fee(BigUint::from(1_000u64))
+            .nonce(Nonce(7))
+            .message("thanks!".to_string())
+            .build()
+            .unwrap();
+
+        let uri = request.to_uri();
+        let parsed = PaymentRequest::from_uri(&uri).unwrap();
+
+        assert_eq!(parsed, request);
+    }
+
+    #[test]
+    fn round_trip_multiple_recipients() {
+        let request = PaymentRequestBuilder::new()
+            .recipient(address(1), BigUint::from(1_000u64))
+            .recipient(address(2), BigUint::from(2_000u64))
+            .recipient(address(3), BigUint::from(3_000u64))
+            .token(TokenLike::Symbol("ETH".to_string()))
+            .build()
+            .unwrap();
+
+        let uri = request.to_uri();
+        let parsed = PaymentRequest::from_uri(&uri).unwrap();
+
+        assert_eq!(parsed.recipients, request.recipients);
+    }
+
+    #[test]
+    fn rejects_duplicate_non_indexed_key() {
+        let uri = format!("zksync:{:#x}?amount=1&token=ETH&amount=2", address(1));
+        assert_eq!(
+            PaymentRequest::from_uri(&uri),
+            Err(PaymentRequestError::DuplicateParameter("amount".to_string()))
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_non_req_parameter() {
+        let uri = format!("zksync:{:#x}?amount=1&token=ETH&label=coffee", address(1));
+        let parsed = PaymentRequest::from_uri(&uri).unwrap();
+        assert_eq!(parsed.recipients[0].amount, BigUint::from(1u64));
+    }
+
+    #[test]
+    fn rejects_unknown_req_parameter() {
+        let uri = format!("zksync:{:#x}?amount=1&token=ETH&req-expiry=123", address(1));
+        assert_eq!(
+            PaymentRequest::from_uri(&uri),
+            Err(PaymentRequestError::UnknownParameter("req-expiry".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_non_packable_amount() {
+        // Not a multiple of 5-bit-mantissa-packable granularity at this magnitude.
+        let huge = BigUint::from_str(&"1".repeat(60)).unwrap();
+        let uri = format!("zksync:{:#x}?amount={}&token=ETH", address(1), huge);
+        assert!(matches!(
+            PaymentRequest::from_uri(&uri),
+            Err(PaymentRequestError::AmountNotPackable(_))
+        ));
+    }
+
+    #[test]
+    fn into_transfers_lowers_every_recipient_with_sequential_nonces() {
+        let request = PaymentRequestBuilder::new()
+            .recipient(address(1), BigUint::from(1_000u64))
+            .recipient(address(2), BigUint::from(2_000u64))
+            .recipient(address(3), BigUint::from(3_000u64))
+            .token(TokenLike::Symbol("ETH".to_string()))
+            .fee(BigUint::from(100u64))
+            .build()
+            .unwrap();
+
+        let from = address(9);
+        let transfers = request.into_transfers(from, TokenId(0), Nonce(5));
+
+        assert_eq!(transfers.len(), 3);
+        for (index, (transfer, recipient)) in transfers.iter().zip(&request.recipients).enumerate() {
+            assert_eq!(transfer.from, from);
+            assert_eq!(transfer.to, recipient.address);
+            assert_eq!(transfer.amount, recipient.amount);
+            assert_eq!(transfer.nonce, Nonce(5 + index as u32));
+        }
+
+        // Only the first transfer carries the request's fee; the rest are free.
+        assert_eq!(transfers[0].fee, BigUint::from(100u64));
+        assert_eq!(transfers[1].fee, BigUint::from(0u64));
+        assert_eq!(transfers[2].fee, BigUint::from(0u64));
+    }
+
+    #[test]
+    fn into_batch_wraps_the_lowered_transfers_unsigned() {
+        let request = PaymentRequestBuilder::new()
+            .recipient(address(1), BigUint::from(1_000u64))
+            .recipient(address(2), BigUint::from(2_000u64))
+            .token(TokenLike::Symbol("ETH".to_string()))
+            .build()
+            .unwrap();
+
+        let batch = request.into_batch(address(9), TokenId(0), Nonce(0));
+
+        assert_eq!(batch.txs.len(), 2);
+        assert!(batch.eth_sign_data.is_none());
+    }
+
+    #[test]
+    fn percent_decode_does_not_panic_on_raw_non_ascii_byte_after_percent() {
+        // "%" immediately followed by a raw (un-encoded) multi-byte UTF-8
+        // character used to panic on a non-char-boundary slice instead of
+        // falling back to passing the bytes through unchanged.
+        assert_eq!(percent_decode("%€"), "%€");
+    }
+
+    #[test]
+    fn from_uri_does_not_panic_on_malformed_percent_encoding_in_message() {
+        let uri = format!("zksync:{:#x}?amount=1&token=ETH&message=%€", address(1));
+        let parsed = PaymentRequest::from_uri(&uri).unwrap();
+        assert_eq!(parsed.message, Some("%€".to_string()));
+    }
+}
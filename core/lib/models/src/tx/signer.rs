@@ -0,0 +1,178 @@
+use zksync_basic_types::Address;
+
+use crate::tx::eth_signature::eth_message_hash;
+use crate::tx::{EthSignData, FranklinTx, PackedEthSignature, SignedFranklinTx, TxEthSignature};
+
+/// Abstraction over where a wallet's signing keys live.
+///
+/// The default implementation ([`PrivateKeySigner`]) keeps the key material
+/// in process, but a wallet can instead implement this trait to route
+/// signing to a hardware wallet or a remote HSM — the private key never has
+/// to enter this crate, only the resulting signatures do.
+pub trait FranklinTxSigner {
+    /// Produces the transaction signature over a transaction's canonical
+    /// bytes (as returned by [`FranklinTx::get_bytes`]), hashed the same
+    /// ecrecover-compatible way as [`Self::eth_sign_message`] so both
+    /// signatures can be checked through the one verification path this
+    /// crate provides ([`TxEthSignature::verify_against`]).
+    fn sign_tx(&self, bytes: &[u8]) -> TxEthSignature;
+
+    /// Produces the Ethereum personal-sign signature over a human-readable
+    /// message, used to build an [`EthSignData`].
+    fn eth_sign_message(&self, message: &str) -> TxEthSignature;
+
+    /// The public key corresponding to this signer's key material.
+    fn public_key(&self) -> Vec<u8>;
+
+    /// The account this signer authorizes transactions for.
+    fn account(&self) -> Address;
+
+    /// Opaque parameters a hardware device needs to re-derive the key used
+    /// by this signer (e.g. a derivation path). Unused by in-memory signers.
+    fn key_derivation_params(&self) -> Vec<u8>;
+}
+
+/// In-memory [`FranklinTxSigner`] that signs with a private key held
+/// directly in process, preserving the crate's current signing behavior.
+pub struct PrivateKeySigner {
+    private_key: Vec<u8>,
+    public_key: Vec<u8>,
+    account: Address,
+}
+
+impl PrivateKeySigner {
+    pub fn new(private_key: Vec<u8>, public_key: Vec<u8>, account: Address) -> Self {
+        Self {
+            private_key,
+            public_key,
+            account,
+        }
+    }
+}
+
+impl FranklinTxSigner for PrivateKeySigner {
+    fn sign_tx(&self, bytes: &[u8]) -> TxEthSignature {
+        TxEthSignature::EthereumSignature(PackedEthSignature(ecdsa_sign_hash(
+            &self.private_key,
+            eth_message_hash(bytes),
+        )))
+    }
+
+    fn eth_sign_message(&self, message: &str) -> TxEthSignature {
+        TxEthSignature::EthereumSignature(PackedEthSignature(ecdsa_sign_hash(
+            &self.private_key,
+            eth_message_hash(message.as_bytes()),
+        )))
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    fn account(&self) -> Address {
+        self.account
+    }
+
+    fn key_derivation_params(&self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+impl SignedFranklinTx {
+    /// Builds a [`SignedFranklinTx`] by delegating both signatures to
+    /// `signer`, returning the tx signature from [`FranklinTxSigner::sign_tx`] alongside the
+    /// resulting [`SignedFranklinTx`] (whose `eth_sign_data` carries the
+    /// Ethereum-side signature over `message`).
+    pub fn sign_with<S: FranklinTxSigner>(
+        tx: FranklinTx,
+        message: String,
+        signer: &S,
+    ) -> (Self, TxEthSignature) {
+        let tx_signature = signer.sign_tx(&tx.get_bytes());
+        let eth_signature = signer.eth_sign_message(&message);
+
+        let signed_tx = Self {
+            tx,
+            eth_sign_data: Some(EthSignData {
+                signature: eth_signature,
+                message,
+            }),
+        };
+
+        (signed_tx, tx_signature)
+    }
+}
+
+fn ecdsa_sign_hash(private_key: &[u8], hash: [u8; 32]) -> Vec<u8> {
+    use parity_crypto::publickey::{sign, Secret};
+
+    let secret = Secret::from_slice(private_key).expect("invalid private key");
+    sign(&secret, &hash.into())
+        .expect("signing should not fail for a valid private key")
+        .into_electrum()
+        .to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::ConditionalTransfer;
+    use crate::TokenId;
+    use num::BigUint;
+    use parity_crypto::publickey::{public_to_address, Generator, KeyPair, Random};
+
+    fn signer_and_account() -> (PrivateKeySigner, Address) {
+        let keypair = KeyPair::from_secret(Generator::generate(Random {}).secret().clone()).expect("valid keypair");
+        let account = public_to_address(keypair.public());
+        let signer = PrivateKeySigner::new(
+            keypair.secret().as_ref().to_vec(),
+            keypair.public().as_ref().to_vec(),
+            account,
+        );
+        (signer, account)
+    }
+
+    fn sample_tx() -> FranklinTx {
+        FranklinTx::ConditionalTransfer(Box::new(ConditionalTransfer::new(
+            Address::zero(),
+            Address::from([1u8; 20]),
+            TokenId(0),
+            BigUint::from(100u64),
+            BigUint::from(1u64),
+            crate::Nonce(0),
+            [7u8; 32],
+            1_000,
+        )))
+    }
+
+    #[test]
+    fn sign_with_round_trips_both_signatures_through_verify_against() {
+        let (signer, account) = signer_and_account();
+        let tx = sample_tx();
+        let tx_bytes = tx.get_bytes();
+
+        let (signed_tx, tx_signature) = SignedFranklinTx::sign_with(tx, "confirm transfer".to_string(), &signer);
+
+        assert!(tx_signature.verify_against(account, &tx_bytes, |_, _, _| false));
+
+        let eth_sign_data = signed_tx.eth_sign_data.as_ref().expect("eth_sign_data is set");
+        assert!(eth_sign_data.verify_against(account, |_, _, _| false));
+    }
+
+    #[test]
+    fn sign_with_rejects_wrong_account() {
+        let (signer, _account) = signer_and_account();
+        let (_, other_account) = signer_and_account();
+        let tx = sample_tx();
+        let tx_bytes = tx.get_bytes();
+
+        let (signed_tx, tx_signature) = SignedFranklinTx::sign_with(tx, "confirm transfer".to_string(), &signer);
+
+        assert!(!tx_signature.verify_against(other_account, &tx_bytes, |_, _, _| false));
+        assert!(!signed_tx
+            .eth_sign_data
+            .as_ref()
+            .unwrap()
+            .verify_against(other_account, |_, _, _| false));
+    }
+}